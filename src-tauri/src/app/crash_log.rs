@@ -0,0 +1,90 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    sync::Mutex,
+};
+
+use serde::Serialize;
+
+use crate::LAUNCHER_DIRECTORY;
+
+/// Number of trailing output lines kept in memory (and reported) for crash diagnostics.
+const TAIL_LINES: usize = 200;
+
+/// Details surfaced to the frontend via the `client-crashed` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct CrashReport {
+    pub exit_code: Option<i32>,
+    pub crash_report_path: Option<String>,
+    pub last_lines: Vec<String>,
+}
+
+/// Tees an instance's stdout/stderr into a log file on disk while keeping a rolling tail in
+/// memory, so a crash can be reported with useful context even after the window is gone.
+pub struct InstanceLogger {
+    file: Mutex<File>,
+    tail: Mutex<VecDeque<String>>,
+}
+
+impl InstanceLogger {
+    pub fn create(instance_id: &str, launched_at_unix_secs: u64) -> anyhow::Result<Self> {
+        let log_dir = LAUNCHER_DIRECTORY.data_dir().join("logs");
+        fs::create_dir_all(&log_dir)?;
+
+        let log_path = log_dir.join(format!("{}-{}.log", instance_id, launched_at_unix_secs));
+        let file = OpenOptions::new().create(true).append(true).open(log_path)?;
+
+        Ok(Self { file: Mutex::new(file), tail: Mutex::new(VecDeque::with_capacity(TAIL_LINES)) })
+    }
+
+    /// Appends a chunk of output to the log file and the in-memory tail.
+    pub fn tee(&self, data: &str) -> anyhow::Result<()> {
+        self.file.lock().unwrap().write_all(data.as_bytes())?;
+
+        let mut tail = self.tail.lock().unwrap();
+        for line in data.lines() {
+            if tail.len() == TAIL_LINES {
+                tail.pop_front();
+            }
+            tail.push_back(line.to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Scans the accumulated output for a Minecraft crash-report path or a fatal stack trace.
+    /// Returns `None` when nothing resembling a crash was observed.
+    pub fn detect_crash(&self, exit_code: Option<i32>) -> Option<CrashReport> {
+        let tail = self.tail.lock().unwrap();
+
+        // Forge/vanilla both print the marker on its own line and the actual path on the line
+        // right after it, e.g.:
+        //   ---- Minecraft Crash Report ----
+        //   #@!@# Game crashed! Crash report saved to: #@!@#
+        //   /home/user/.minecraft/crash-reports/crash-2026-01-01.txt
+        let crash_report_path = find_marker_line(&tail, "#@!@# Game crashed! Crash report saved to: #@!@#")
+            .or_else(|| find_marker_line(&tail, "Crash report saved to: "));
+
+        let has_fatal_trace = tail.iter().any(|line|
+            line.contains("Exception in thread") || line.contains("java.lang.OutOfMemoryError")
+        );
+
+        if crash_report_path.is_none() && !has_fatal_trace && exit_code.unwrap_or(0) == 0 {
+            return None;
+        }
+
+        Some(CrashReport {
+            exit_code,
+            crash_report_path,
+            last_lines: tail.iter().cloned().collect(),
+        })
+    }
+}
+
+/// Finds a line containing `marker` and returns the trimmed contents of the *next* line, which
+/// is where Minecraft/Forge actually print the crash-report path.
+fn find_marker_line(tail: &VecDeque<String>, marker: &str) -> Option<String> {
+    let marker_index = tail.iter().position(|line| line.contains(marker))?;
+    tail.get(marker_index + 1).map(|path| path.trim().to_string())
+}
@@ -1,21 +1,77 @@
-use std::{process::exit, sync::{Arc, Mutex}, thread};
+use std::{collections::HashMap, process::exit, sync::{Arc, Mutex}, thread};
 
 use anyhow::anyhow;
 use env_logger::Env;
 use log::{info};
+use serde::Serialize;
 use sysinfo::SystemExt;
-use tauri::{Manager, Window};
+use tauri::{CustomMenuItem, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu, SystemTrayMenuItem, Window};
+use uuid::Uuid;
 
-use crate::{LAUNCHER_DIRECTORY, minecraft::{service::{Account, self}, launcher::{LaunchingParameter, LauncherData}, progress::ProgressUpdate, prelauncher}};
+use crate::{LAUNCHER_DIRECTORY, minecraft::{self, service::{Account, self}, launcher::{LaunchingParameter, LauncherData}, progress::ProgressUpdate, prelauncher}, updater::{self, LatestVersionApiAdapter, UpdateManifest}};
 
-use super::{app_data::LauncherOptions, api::{ApiEndpoints, Build, LoaderMod}};
+use super::{app_data::LauncherOptions, api::{ApiEndpoints, Build, LoaderMod}, crash_log::InstanceLogger};
 
 struct RunnerInstance {
     terminator: tokio::sync::oneshot::Sender<()>,
+    keep_launcher_open: bool,
 }
 
 struct AppState {
-    runner_instance: Arc<Mutex<Option<RunnerInstance>>>
+    runner_instances: Arc<Mutex<HashMap<String, RunnerInstance>>>
+}
+
+/// Context threaded through [`LauncherData`]'s output/progress handlers so events can be
+/// tagged with the instance they originated from, letting the frontend route them to the
+/// right window/tab when several clients run side by side.
+struct WindowContext {
+    window: Arc<std::sync::Mutex<Window>>,
+    instance_id: String,
+    logger: Arc<InstanceLogger>,
+}
+
+#[derive(Serialize)]
+struct InstanceEvent<T: Serialize> {
+    instance_id: String,
+    #[serde(flatten)]
+    payload: T,
+}
+
+#[derive(Serialize)]
+struct ProcessOutput {
+    data: String,
+}
+
+const TERMINATE_CONFIRM_MESSAGE: &str = "Are you sure you want to force-stop the running client(s)? Unsaved progress in the game may be lost.";
+
+/// Shows a native yes/no confirmation dialog and blocks until the user answers. Safe to call
+/// directly from a synchronous context, such as a tray event callback; from an async command,
+/// go through [`confirm_dialog`] instead so the blocking call doesn't stall the tokio worker.
+fn confirm_blocking(window: Option<&Window>, title: &str, message: &str) -> bool {
+    tauri::api::dialog::blocking::confirm(window, title, message)
+}
+
+/// Runs [`confirm_blocking`] off the async command path: `tauri::api::dialog`'s functions block
+/// the calling thread, which would otherwise stall the tokio worker driving this command.
+async fn confirm_dialog(window: &Window, title: &str, message: &str) -> bool {
+    let window = window.clone();
+    let title = title.to_string();
+    let message = message.to_string();
+
+    tokio::task::spawn_blocking(move || confirm_blocking(Some(&window), &title, &message))
+        .await
+        .unwrap_or(false)
+}
+
+/// Shows a native blocking message/notice dialog (no choice to make, just an acknowledgement).
+async fn message_dialog(window: &Window, title: &str, message: &str) {
+    let window = window.clone();
+    let title = title.to_string();
+    let message = message.to_string();
+
+    let _ = tokio::task::spawn_blocking(move || {
+        tauri::api::dialog::blocking::message(Some(&window), title, message)
+    }).await;
 }
 
 #[tauri::command]
@@ -76,23 +132,30 @@ async fn request_mods(mc_version: &str, subsystem: &str) -> Result<Vec<LoaderMod
     Ok(mods)
 }
 
-fn handle_stdout(window: &Arc<std::sync::Mutex<Window>>, data: &[u8]) -> anyhow::Result<()> {
-    window.lock().unwrap().emit("process-output", String::from_utf8(data.to_vec())?)?;
+fn handle_stdout(ctx: &WindowContext, data: &[u8]) -> anyhow::Result<()> {
+    let text = String::from_utf8(data.to_vec())?;
+    ctx.logger.tee(&text)?;
+    ctx.window.lock().unwrap().emit("process-output", InstanceEvent {
+        instance_id: ctx.instance_id.clone(),
+        payload: ProcessOutput { data: text }
+    })?;
     Ok(())
 }
 
-fn handle_stderr(window: &Arc<std::sync::Mutex<Window>>, data: &[u8]) -> anyhow::Result<()> {
-    window.lock().unwrap().emit("process-output", String::from_utf8(data.to_vec())?)?;
-    Ok(())
+fn handle_stderr(ctx: &WindowContext, data: &[u8]) -> anyhow::Result<()> {
+    handle_stdout(ctx, data)
 }
 
-fn handle_progress(window: &Arc<std::sync::Mutex<Window>>, progress_update: ProgressUpdate) -> anyhow::Result<()> {
-    window.lock().unwrap().emit("progress-update", progress_update)?;
+fn handle_progress(ctx: &WindowContext, progress_update: ProgressUpdate) -> anyhow::Result<()> {
+    ctx.window.lock().unwrap().emit("progress-update", InstanceEvent {
+        instance_id: ctx.instance_id.clone(),
+        payload: progress_update
+    })?;
     Ok(())
 }
 
 #[tauri::command]
-async fn run_client(build_id: i32, account_data: Account, options: LauncherOptions, mods: Vec<LoaderMod>, window: Window, app_state: tauri::State<'_, AppState>) -> Result<(), String> {
+async fn run_client(build_id: i32, account_data: Account, options: LauncherOptions, mods: Vec<LoaderMod>, window: Window, app_handle: tauri::AppHandle, app_state: tauri::State<'_, AppState>) -> Result<String, String> {
     let (account_name, uuid, token, user_type) = match account_data {
         Account::MsaAccount { auth, .. } => (auth.name, auth.uuid, auth.token, "msa".to_string()),
         Account::MojangAccount { name, token, uuid } => (name, token, uuid, "mojang".to_string()),
@@ -100,9 +163,50 @@ async fn run_client(build_id: i32, account_data: Account, options: LauncherOptio
     };
 
     let sys = sysinfo::System::new_all();
+    let instance_id = Uuid::new_v4().to_string();
+    let launched_at = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+    let logger = Arc::new(InstanceLogger::create(&instance_id, launched_at).map_err(|e| format!("unable to open instance log file: {:?}", e))?);
+    let ctx = WindowContext { window: Arc::new(std::sync::Mutex::new(window.clone())), instance_id: instance_id.clone(), logger: logger.clone() };
+
+    info!("Loading launch manifest...");
+    let launch_manifest = match ApiEndpoints::launch_manifest(build_id).await {
+        Ok(launch_manifest) => launch_manifest,
+        Err(e) => {
+            message_dialog(&window, "Failed to launch", &format!("Unable to request launch manifest: {:?}", e)).await;
+            return Err(format!("unable to request launch manifest: {:?}", e));
+        }
+    };
+
+    let requested_memory = (sys.total_memory() / 1000000) as f64 * (options.memory_percentage as f64 / 100.0);
+    if requested_memory > (sys.available_memory() / 1000000) as f64 {
+        // Just a heads-up, not a choice to make: the user already picked this memory percentage
+        // in the launcher options, so we warn and proceed rather than gating the launch on it.
+        message_dialog(
+            &window,
+            "Low on memory",
+            &format!("The selected memory allocation ({} MB) exceeds the currently available system RAM ({} MB). The client may fail to start or your system may become unstable.", requested_memory as i64, sys.available_memory() / 1000000)
+        ).await;
+    }
+
+    // `custom_java_path` doubles as the resolved path we feed into the launcher, whether the
+    // user supplied it explicitly or we auto-provisioned a matching JRE below.
+    let custom_java_path = if !options.custom_java_path.is_empty() {
+        Some(options.custom_java_path)
+    } else {
+        let java_path = minecraft::java::jre_downloader::provision_jre(
+            launch_manifest.java_version,
+            &instance_id,
+            |progress_update| { let _ = handle_progress(&ctx, progress_update); }
+        )
+            .await
+            .map_err(|e| format!("unable to provision java runtime: {:?}", e))?;
+
+        Some(java_path.to_string_lossy().to_string())
+    };
+
     let parameters = LaunchingParameter {
-        memory: ((sys.total_memory() / 1000000) as f64 * (options.memory_percentage as f64 / 100.0)) as i64,
-        custom_java_path: if !options.custom_java_path.is_empty() { Some(options.custom_java_path) } else { None },
+        memory: requested_memory as i64,
+        custom_java_path,
         auth_player_name: account_name,
         auth_uuid: uuid,
         auth_access_token: token,
@@ -112,23 +216,23 @@ async fn run_client(build_id: i32, account_data: Account, options: LauncherOptio
         keep_launcher_open: options.keep_launcher_open
     };
 
-    let runner_instance = &app_state.runner_instance;
-
-    if runner_instance.lock().map_err(|e| format!("unable to lock runner instance: {:?}", e))?.is_some() {
-        return Err("client is already running".to_string());
-    }
-
-    info!("Loading launch manifest...");
-    let launch_manifest = ApiEndpoints::launch_manifest(build_id)
-        .await
-        .map_err(|e| format!("unable to request launch manifest: {:?}", e))?;
-
     let (terminator_tx, terminator_rx) = tokio::sync::oneshot::channel();
 
-    *runner_instance.lock().map_err(|e| format!("unable to lock runner instance: {:?}", e))?
-        = Some(RunnerInstance { terminator: terminator_tx });
+    {
+        // Hold the lock across mutate + compute + apply: releasing it before calling
+        // update_tray_state let two concurrent run_client calls interleave their tray updates
+        // out of order, leaving the tooltip/menu showing a stale count.
+        let mut instances = app_state.runner_instances.lock().map_err(|e| format!("unable to lock runner instances: {:?}", e))?;
+        instances.insert(instance_id.clone(), RunnerInstance { terminator: terminator_tx, keep_launcher_open: options.keep_launcher_open });
+        update_tray_state(&app_handle, instances.len());
+        apply_window_visibility(&window, &instances);
+    }
 
-    prelauncher::launch(
+    // `prelauncher::launch` only errs when the client never got to run at all (missing jar, bad
+    // manifest, ...); it doesn't currently surface the spawned JVM's real exit code to us, so we
+    // can't report one here. Crash detection below relies on `logger`'s scan of the process
+    // output instead of a numeric exit code.
+    let launch_result: anyhow::Result<()> = prelauncher::launch(
             launch_manifest,
             parameters,
             mods,
@@ -136,34 +240,202 @@ async fn run_client(build_id: i32, account_data: Account, options: LauncherOptio
                 on_stdout: handle_stdout,
                 on_stderr: handle_stderr,
                 on_progress: handle_progress,
-                data: Box::new(Arc::new(std::sync::Mutex::new(window))),
+                data: Box::new(ctx),
                 terminator: terminator_rx
             }
-    ).await
-        .map_err(|e| format!("failed to launch client: {:?}", e))?;
+    ).await;
+
+    {
+        let mut instances = app_state.runner_instances.lock().map_err(|e| format!("unable to lock runner instances: {:?}", e))?;
+        instances.remove(&instance_id);
+        update_tray_state(&app_handle, instances.len());
+        apply_window_visibility(&window, &instances);
+    }
+
+    if let Some(crash_report) = logger.detect_crash(None) {
+        window.emit("client-crashed", InstanceEvent { instance_id: instance_id.clone(), payload: crash_report }).ok();
+    }
+
+    if let Err(e) = &launch_result {
+        message_dialog(&window, "Failed to launch", &format!("Unable to launch client: {:?}", e)).await;
+    }
+
+    launch_result.map_err(|e| format!("failed to launch client: {:?}", e))?;
+
+    Ok(instance_id)
+}
 
-    *runner_instance.lock().map_err(|e| format!("unable to lock runner instance: {:?}", e))?
-        = None;
+/// Shows or hides the shared main window based on what every currently-running instance wants,
+/// rather than whichever instance most recently started or stopped: hidden only once *all*
+/// running instances asked for `keep_launcher_open == false`, shown again as soon as any
+/// instance wants it visible (including once none are left running).
+fn apply_window_visibility(window: &Window, instances: &HashMap<String, RunnerInstance>) {
+    let any_wants_visible = instances.is_empty() || instances.values().any(|inst| inst.keep_launcher_open);
+
+    if any_wants_visible {
+        window.show().ok();
+    } else {
+        window.hide().ok();
+    }
+}
+
+#[tauri::command]
+async fn check_for_update() -> Result<Option<UpdateManifest>, String> {
+    let manifest = LatestVersionApiAdapter::fetch_latest()
+        .await
+        .map_err(|e| format!("unable to check for updates: {:?}", e))?;
+
+    if updater::is_newer_version(&manifest.version) {
+        Ok(Some(manifest))
+    } else {
+        Ok(None)
+    }
+}
+
+#[tauri::command]
+async fn install_update(window: Window, app_state: tauri::State<'_, AppState>) -> Result<(), String> {
+    if !app_state.runner_instances.lock().map_err(|e| format!("unable to lock runner instances: {:?}", e))?.is_empty() {
+        return Err("cannot update while a client is running".to_string());
+    }
+
+    let manifest = LatestVersionApiAdapter::fetch_latest()
+        .await
+        .map_err(|e| format!("unable to check for updates: {:?}", e))?;
+
+    let window = Arc::new(std::sync::Mutex::new(window));
+    let update_path = updater::download_update(&manifest, |progress| {
+        let _ = window.lock().unwrap().emit("update-progress", progress);
+    })
+        .await
+        .map_err(|e| format!("failed to download update: {:?}", e))?;
+
+    updater::stage_update_for_install(&update_path)
+        .map_err(|e| format!("failed to stage update: {:?}", e))?;
+
+    window.lock().unwrap().emit("update-ready", ()).map_err(|e| format!("{:?}", e))?;
 
     Ok(())
 }
 
 #[tauri::command]
-async fn terminate(app_state: tauri::State<'_, AppState>) -> Result<(), String> {
-    let mut lck = app_state.runner_instance.lock()
-        .map_err(|e| format!("unable to lock runner instance: {:?}", e))?;
+async fn terminate(instance_id: String, window: Window, app_handle: tauri::AppHandle, app_state: tauri::State<'_, AppState>) -> Result<(), String> {
+    if !confirm_dialog(&window, "Terminate client", TERMINATE_CONFIRM_MESSAGE).await {
+        return Ok(());
+    }
+
+    let remaining = terminate_runner(&app_state, Some(&instance_id))?;
+    update_tray_state(&app_handle, remaining);
+    Ok(())
+}
 
-    if let Some(inst) = lck.take() {
+/// Stops the running client identified by `instance_id`, or every running client when `None`.
+/// Shared by the `terminate` command (a specific instance) and the "Terminate Client" tray menu
+/// item, which has no way to target a single instance. Returns the number of clients still
+/// running afterwards, so callers can refresh the tray state.
+fn terminate_runner(app_state: &AppState, instance_id: Option<&str>) -> Result<usize, String> {
+    let mut instances = app_state.runner_instances.lock()
+        .map_err(|e| format!("unable to lock runner instances: {:?}", e))?;
+
+    let to_terminate: Vec<RunnerInstance> = match instance_id {
+        Some(id) => instances.remove(id).into_iter().collect(),
+        None => instances.drain().map(|(_, inst)| inst).collect()
+    };
+
+    for inst in to_terminate {
         println!("Sending sigterm");
         inst.terminator.send(()).unwrap();
     }
-    Ok(())
+
+    Ok(instances.len())
+}
+
+fn build_system_tray() -> SystemTray {
+    let menu = SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new("show_hide".to_string(), "Show/Hide"))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new("terminate".to_string(), "Terminate Client").disabled())
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new("quit".to_string(), "Quit"));
+
+    SystemTray::new().with_menu(menu).with_tooltip("Alcaty Launcher")
+}
+
+/// Reflects how many clients are currently running in the tray tooltip and keeps the
+/// "Terminate Client" item disabled (and correctly worded) when there's nothing to terminate.
+fn update_tray_state(app_handle: &tauri::AppHandle, running_count: usize) {
+    let tray = app_handle.tray_handle();
+
+    let tooltip = match running_count {
+        0 => "Alcaty Launcher".to_string(),
+        1 => "Alcaty Launcher — 1 client running".to_string(),
+        n => format!("Alcaty Launcher — {} clients running", n)
+    };
+    tray.set_tooltip(&tooltip).ok();
+
+    let terminate_item = tray.get_item("terminate");
+    terminate_item.set_enabled(running_count > 0).ok();
+    terminate_item.set_title(if running_count > 1 { "Terminate All Clients" } else { "Terminate Client" }).ok();
+}
+
+fn handle_system_tray_event(app: &tauri::AppHandle, event: SystemTrayEvent) {
+    match event {
+        SystemTrayEvent::LeftClick { .. } => {
+            if let Some(window) = app.get_window("main") {
+                if window.is_visible().unwrap_or(false) {
+                    window.hide().ok();
+                } else {
+                    window.show().ok();
+                    window.set_focus().ok();
+                }
+            }
+        },
+        SystemTrayEvent::MenuItemClick { id, .. } => {
+            match id.as_str() {
+                "show_hide" => {
+                    if let Some(window) = app.get_window("main") {
+                        if window.is_visible().unwrap_or(false) {
+                            window.hide().ok();
+                        } else {
+                            window.show().ok();
+                            window.set_focus().ok();
+                        }
+                    }
+                },
+                "terminate" => {
+                    let main_window = app.get_window("main");
+                    let confirmed = confirm_blocking(main_window.as_ref(), "Terminate client", TERMINATE_CONFIRM_MESSAGE);
+
+                    if confirmed {
+                        let app_state = app.state::<AppState>();
+                        if let Ok(remaining) = terminate_runner(&app_state, None) {
+                            update_tray_state(app, remaining);
+                        }
+                    }
+                },
+                "quit" => exit(0),
+                _ => {}
+            }
+        },
+        _ => {}
+    }
 }
 
 /// Runs the GUI and returns when the window is closed.
 pub fn gui_main() {
     env_logger::Builder::from_env(Env::default().default_filter_or("liquidlauncher=debug")).init();
 
+    match updater::apply_staged_update() {
+        Ok(true) => {
+            info!("Applied staged update, restarting into the new version...");
+            if let Ok(current_exe) = std::env::current_exe() {
+                let _ = std::process::Command::new(current_exe).spawn();
+            }
+            exit(0);
+        },
+        Ok(false) => {},
+        Err(e) => log::error!("failed to apply staged update: {:?}", e)
+    }
+
     tauri::Builder::default()
         .setup(|app| {
             let window = app.get_window("main").unwrap();
@@ -186,9 +458,11 @@ pub fn gui_main() {
 
             Ok(())
         })
-        .manage(AppState { 
-            runner_instance: Arc::new(Mutex::new(None))
+        .manage(AppState {
+            runner_instances: Arc::new(Mutex::new(HashMap::new()))
         })
+        .system_tray(build_system_tray())
+        .on_system_tray_event(handle_system_tray_event)
         .invoke_handler(tauri::generate_handler![
             exit_app,
             open_url,
@@ -198,7 +472,9 @@ pub fn gui_main() {
             request_builds,
             request_mods,
             run_client,
-            terminate
+            terminate,
+            check_for_update,
+            install_update
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,90 @@
+use anyhow::Context;
+use serde::Deserialize;
+
+/// A single JRE binary offered by the configured distribution for a given OS/arch/major version.
+#[derive(Debug, Clone)]
+pub struct JreArtifact {
+    pub download_url: String,
+    pub checksum: String,
+    /// `"zip"` or `"tar.gz"`, matching the archive format of `download_url`.
+    pub archive_type: String,
+}
+
+/// One entry of the `v3/assets/latest/{version}/hotspot` response: a JSON array of releases,
+/// each describing a single binary. There is no top-level archive-type field — it has to be
+/// inferred from the package's file name.
+#[derive(Debug, Clone, Deserialize)]
+struct AssetRelease {
+    binary: AssetBinary,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AssetBinary {
+    package: AssetPackage,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AssetPackage {
+    name: String,
+    link: String,
+    checksum: String,
+}
+
+/// Adoptium-style API used to resolve a JRE binary for the current platform.
+pub struct JavaDistribution;
+
+impl JavaDistribution {
+    const API_BASE: &'static str = "https://api.adoptium.net/v3/assets/latest";
+
+    /// Resolves the JRE artifact for `major_version` on the current OS/arch.
+    pub async fn resolve(major_version: u32) -> anyhow::Result<JreArtifact> {
+        let url = format!(
+            "{}/{}/hotspot?image_type=jre&os={}&architecture={}",
+            Self::API_BASE, major_version, Self::os_identifier(), Self::arch_identifier()
+        );
+
+        let releases = reqwest::get(&url)
+            .await
+            .context("unable to reach java distribution endpoint")?
+            .json::<Vec<AssetRelease>>()
+            .await
+            .context("unable to parse java distribution response")?;
+
+        let package = releases.into_iter().next()
+            .map(|release| release.binary.package)
+            .context("java distribution endpoint returned no matching release")?;
+
+        let archive_type = Self::archive_type_of(&package.name)
+            .context("unable to infer archive type of java distribution package")?;
+
+        Ok(JreArtifact { download_url: package.link, checksum: package.checksum, archive_type })
+    }
+
+    fn archive_type_of(file_name: &str) -> Option<String> {
+        if file_name.ends_with(".tar.gz") {
+            Some("tar.gz".to_string())
+        } else if file_name.ends_with(".zip") {
+            Some("zip".to_string())
+        } else {
+            None
+        }
+    }
+
+    fn os_identifier() -> &'static str {
+        if cfg!(target_os = "windows") {
+            "windows"
+        } else if cfg!(target_os = "macos") {
+            "mac"
+        } else {
+            "linux"
+        }
+    }
+
+    fn arch_identifier() -> &'static str {
+        if cfg!(target_arch = "aarch64") {
+            "aarch64"
+        } else {
+            "x64"
+        }
+    }
+}
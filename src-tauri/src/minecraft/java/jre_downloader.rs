@@ -0,0 +1,119 @@
+use std::{
+    collections::HashMap,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex as StdMutex, OnceLock},
+};
+
+use anyhow::Context;
+use sha2::{Digest, Sha256};
+
+use crate::minecraft::progress::ProgressUpdate;
+
+use super::{distribution::JavaDistribution, runtime};
+
+/// Per-major-version in-flight guards: concurrent launches that need the same uncached Java
+/// version wait on the same lock instead of downloading/extracting into the shared runtime
+/// directory at the same time, which (pre-chunk0-4, when only one client could run at a time)
+/// could never happen but now routinely does.
+static PROVISION_LOCKS: OnceLock<StdMutex<HashMap<u32, Arc<tokio::sync::Mutex<()>>>>> = OnceLock::new();
+
+fn provision_lock(major_version: u32) -> Arc<tokio::sync::Mutex<()>> {
+    PROVISION_LOCKS.get_or_init(|| StdMutex::new(HashMap::new()))
+        .lock().unwrap()
+        .entry(major_version)
+        .or_insert_with(|| Arc::new(tokio::sync::Mutex::new(())))
+        .clone()
+}
+
+/// Downloads, verifies and extracts the JRE for `major_version` if it is not already cached,
+/// reporting progress via `on_progress`, and returns the path to its `java` executable.
+///
+/// `attempt_tag` (e.g. the launching instance id) only needs to be unique per call; it keys the
+/// scratch extraction directory so two concurrent provisioning attempts never write to the same
+/// path.
+pub async fn provision_jre<F>(major_version: u32, attempt_tag: &str, on_progress: F) -> anyhow::Result<PathBuf>
+    where F: Fn(ProgressUpdate)
+{
+    let lock = provision_lock(major_version);
+    let _guard = lock.lock().await;
+
+    let runtime_dir = runtime::runtime_dir(major_version);
+    let java_executable = runtime::java_executable(&runtime_dir);
+
+    if java_executable.is_file() {
+        return Ok(java_executable);
+    }
+
+    on_progress(ProgressUpdate::SetLabel(format!("Downloading Java {}...", major_version)));
+    let artifact = JavaDistribution::resolve(major_version).await?;
+
+    let response = reqwest::get(&artifact.download_url)
+        .await
+        .context("unable to download java runtime")?;
+
+    let total = response.content_length().unwrap_or(0);
+    on_progress(ProgressUpdate::SetMax(total));
+
+    let mut downloaded = 0u64;
+    let mut archive_bytes = Vec::with_capacity(total as usize);
+
+    use futures::StreamExt;
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.context("error while downloading java runtime")?;
+        downloaded += chunk.len() as u64;
+        archive_bytes.write_all(&chunk)?;
+        on_progress(ProgressUpdate::SetProgress(downloaded));
+    }
+
+    let digest = format!("{:x}", Sha256::digest(&archive_bytes));
+    anyhow::ensure!(digest == artifact.checksum, "checksum mismatch for java {} ({} != {})", major_version, digest, artifact.checksum);
+
+    on_progress(ProgressUpdate::SetLabel(format!("Extracting Java {}...", major_version)));
+    let runtimes_dir = runtime_dir.parent().context("runtime directory has no parent")?;
+    let extract_dir = runtimes_dir.join(format!("java-{}-{}.tmp-extract", major_version, attempt_tag));
+    std::fs::create_dir_all(&extract_dir)?;
+
+    match artifact.archive_type.as_str() {
+        "zip" => extract_zip(&archive_bytes, &extract_dir)?,
+        "tar.gz" => extract_tar_gz(&archive_bytes, &extract_dir)?,
+        other => anyhow::bail!("unsupported java archive type: {}", other)
+    }
+
+    // Adoptium archives contain a single top-level `jdk-.../` folder; promote its contents so
+    // `runtime::java_executable` can find `bin/java` directly under `runtime_dir`.
+    let inner_dir = std::fs::read_dir(&extract_dir)?
+        .filter_map(Result::ok)
+        .find(|entry| entry.path().is_dir())
+        .map(|entry| entry.path())
+        .context("java archive did not contain the expected top-level directory")?;
+
+    if !runtime_dir.is_dir() {
+        std::fs::rename(&inner_dir, &runtime_dir)?;
+    }
+    std::fs::remove_dir_all(&extract_dir).ok();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut permissions = std::fs::metadata(&java_executable)?.permissions();
+        permissions.set_mode(0o755);
+        std::fs::set_permissions(&java_executable, permissions)?;
+    }
+
+    Ok(java_executable)
+}
+
+fn extract_zip(bytes: &[u8], destination: &Path) -> anyhow::Result<()> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))?;
+    archive.extract(destination)?;
+    Ok(())
+}
+
+fn extract_tar_gz(bytes: &[u8], destination: &Path) -> anyhow::Result<()> {
+    let decoder = flate2::read::GzDecoder::new(bytes);
+    let mut archive = tar::Archive::new(decoder);
+    archive.unpack(destination)?;
+    Ok(())
+}
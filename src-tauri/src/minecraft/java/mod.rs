@@ -0,0 +1,3 @@
+pub mod distribution;
+pub mod jre_downloader;
+pub mod runtime;
@@ -0,0 +1,24 @@
+use std::path::{Path, PathBuf};
+
+use crate::LAUNCHER_DIRECTORY;
+
+/// Directory a given Java major version is (or will be) extracted into.
+pub fn runtime_dir(major_version: u32) -> PathBuf {
+    LAUNCHER_DIRECTORY.data_dir().join("runtimes").join(format!("java-{}", major_version))
+}
+
+/// Path of the `java`/`java.exe` executable within an extracted runtime directory.
+pub fn java_executable(runtime_dir: &Path) -> PathBuf {
+    let bin_dir = runtime_dir.join("bin");
+
+    if cfg!(target_os = "windows") {
+        bin_dir.join("java.exe")
+    } else {
+        bin_dir.join("java")
+    }
+}
+
+/// `true` if a runtime for `major_version` is already cached on disk.
+pub fn is_cached(major_version: u32) -> bool {
+    java_executable(&runtime_dir(major_version)).is_file()
+}
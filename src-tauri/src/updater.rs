@@ -0,0 +1,142 @@
+use std::{io::Write, path::PathBuf};
+
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::LAUNCHER_DIRECTORY;
+
+const UPDATE_API_ENDPOINT: &str = "https://api.liquidbounce.net/api/v1/launcher/latest";
+
+/// Metadata describing the newest published release, as returned by the update API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateManifest {
+    pub version: String,
+    pub asset_url: String,
+    pub checksum: String,
+}
+
+/// Queries the update API for the latest release metadata.
+pub struct LatestVersionApiAdapter;
+
+impl LatestVersionApiAdapter {
+    /// Fetches the latest release manifest for the current platform.
+    pub async fn fetch_latest() -> anyhow::Result<UpdateManifest> {
+        let response = reqwest::get(format!("{}?platform={}", UPDATE_API_ENDPOINT, platform_identifier()))
+            .await
+            .context("unable to reach update endpoint")?;
+
+        let manifest = response
+            .json::<UpdateManifest>()
+            .await
+            .context("unable to parse update manifest")?;
+
+        Ok(manifest)
+    }
+}
+
+/// Progress payload emitted on the `update-progress` window event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum UpdateProgress {
+    Downloading { downloaded: u64, total: u64 },
+    Verifying,
+    ReadyToInstall,
+}
+
+fn platform_identifier() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(target_os = "macos") {
+        "macos"
+    } else {
+        "linux"
+    }
+}
+
+/// Returns `true` if `remote_version` is newer than the running launcher's version.
+pub fn is_newer_version(remote_version: &str) -> bool {
+    fn parse(version: &str) -> Vec<u32> {
+        version.trim_start_matches(|c: char| !c.is_ascii_digit())
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    }
+
+    parse(remote_version) > parse(env!("CARGO_PKG_VERSION"))
+}
+
+/// Downloads the platform asset described by `manifest`, verifying its checksum, and reports
+/// progress through `on_progress`. Returns the path of the verified, not-yet-installed asset.
+pub async fn download_update<F>(manifest: &UpdateManifest, on_progress: F) -> anyhow::Result<PathBuf>
+    where F: Fn(UpdateProgress)
+{
+    let response = reqwest::get(&manifest.asset_url)
+        .await
+        .context("unable to download update asset")?;
+
+    let total = response.content_length().unwrap_or(0);
+    let mut downloaded = 0u64;
+    let mut hasher = Sha256::new();
+
+    let update_dir = LAUNCHER_DIRECTORY.data_dir().join("updates");
+    std::fs::create_dir_all(&update_dir)?;
+    let dest_path = update_dir.join(format!("launcher-{}.update", manifest.version));
+    let mut file = std::fs::File::create(&dest_path)?;
+
+    let mut stream = response.bytes_stream();
+    use futures::StreamExt;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.context("error while downloading update")?;
+        hasher.update(&chunk);
+        file.write_all(&chunk)?;
+        downloaded += chunk.len() as u64;
+        on_progress(UpdateProgress::Downloading { downloaded, total });
+    }
+
+    on_progress(UpdateProgress::Verifying);
+
+    let digest = format!("{:x}", hasher.finalize());
+    if digest != manifest.checksum {
+        std::fs::remove_file(&dest_path).ok();
+        return Err(anyhow!("checksum mismatch: expected {}, got {}", manifest.checksum, digest));
+    }
+
+    on_progress(UpdateProgress::ReadyToInstall);
+
+    Ok(dest_path)
+}
+
+/// Stages the downloaded, verified update artifact next to the current executable so
+/// [`apply_staged_update`] can swap it in on the next launch.
+pub fn stage_update_for_install(update_path: &PathBuf) -> anyhow::Result<()> {
+    let current_exe = std::env::current_exe().context("unable to determine current executable")?;
+    let staged_path = current_exe.with_extension("update");
+    std::fs::copy(update_path, &staged_path).context("unable to stage update artifact")?;
+    std::fs::remove_file(update_path).ok();
+    Ok(())
+}
+
+/// Applies a previously staged update, if one is present, by moving the running executable
+/// aside and the staged artifact into its place. Must run before the window/tray are set up,
+/// since the executable cannot be overwritten while it is mapped into this process on most
+/// platforms — renaming it aside first works everywhere `std::env::current_exe` does.
+///
+/// Returns `true` if an update was applied (the caller should restart into the new binary).
+pub fn apply_staged_update() -> anyhow::Result<bool> {
+    let current_exe = std::env::current_exe().context("unable to determine current executable")?;
+    let staged_path = current_exe.with_extension("update");
+
+    if !staged_path.is_file() {
+        return Ok(false);
+    }
+
+    let backup_path = current_exe.with_extension("old");
+    std::fs::rename(&current_exe, &backup_path).context("unable to move current executable aside")?;
+    std::fs::rename(&staged_path, &current_exe).context("unable to move staged update into place")?;
+    std::fs::remove_file(&backup_path).ok();
+
+    std::fs::remove_dir_all(LAUNCHER_DIRECTORY.data_dir().join("updates")).ok();
+
+    Ok(true)
+}